@@ -1,55 +1,639 @@
 #![windows_subsystem = "windows"]
 
 use eframe::egui::{self};
-use egui_plot::{Line, Plot, PlotBounds};
+use egui_plot::{Legend, Line, Plot, PlotBounds};
 use ping::ping;
+use std::collections::VecDeque;
+use std::io::Write;
 use std::net::ToSocketAddrs;
+use std::path::Path;
 use std::sync::{mpsc, Arc, RwLock};
 use std::thread::{self};
 use std::time::{Duration, Instant};
 
+/// Default number of samples kept per host's ring buffer.
+const DEFAULT_WINDOW_SIZE: usize = 600;
+
+/// Default time between ping attempts.
+const PING_INTERVAL_MS: u64 = 1000;
+/// Default time to wait for a single reply before treating it as lost.
+const PING_TIMEOUT_MS: u64 = 1000;
+
+/// A palette of distinct colors cycled through for each host's line.
+const HOST_COLORS: [egui::Color32; 6] = [
+    egui::Color32::from_rgb(0x4e, 0x9a, 0xff),
+    egui::Color32::from_rgb(0xff, 0x6e, 0x54),
+    egui::Color32::from_rgb(0x5a, 0xd8, 0x6a),
+    egui::Color32::from_rgb(0xff, 0xc9, 0x3c),
+    egui::Color32::from_rgb(0xc3, 0x6a, 0xff),
+    egui::Color32::from_rgb(0x3c, 0xd8, 0xd8),
+];
+
+/// Shared, mutable state for a single ping target: the address being
+/// pinged and the most recent error reported by its worker thread.
+struct PingHost {
+    address: String,
+    error: String,
+}
+
+/// Shared state for the whole app: the cadence worker threads ping at.
+/// Each host's own `Arc<RwLock<PingHost>>` is handed directly to its
+/// worker thread, so this only needs to carry the settings every worker
+/// reads fresh on each iteration, letting a slider change take effect
+/// immediately.
+struct PingSharedState {
+    interval_ms: u64,
+    timeout_ms: u64,
+}
+
+/// Fixed-capacity ring buffer of `[time, value]` samples, used for each
+/// host's plotted history so memory stays bounded no matter how long a
+/// session runs. Once full, pushing a new sample drops the oldest one.
+///
+/// `time` is supplied by the caller rather than generated internally, so
+/// every host can be pushed onto a single shared clock (see `PingApp`'s
+/// `sample_clock`) instead of each restarting its own counter from zero
+/// when added mid-session.
+///
+/// best/worst/average/jitter over the window are maintained incrementally
+/// as samples are pushed and evicted, rather than rescanned on every
+/// arrival: `sum`/`count` adjust by a constant amount per sample, best/
+/// worst ride a monotonic deque each (the standard sliding-window-min/max
+/// trick), and jitter tracks running sum/count of consecutive-sample
+/// deltas alongside a deque so the oldest delta leaves with the sample
+/// that produced it.
+struct RingBuffer {
+    samples: VecDeque<[f64; 2]>,
+    capacity: usize,
+    last_time: f64,
+    sum: f64,
+    count: usize,
+    min_deque: VecDeque<(f64, f64)>,    // (time, rtt), value non-decreasing from the front
+    max_deque: VecDeque<(f64, f64)>,    // (time, rtt), value non-increasing from the front
+    jitter_sum: f64,
+    jitter_count: usize,
+    jitter_deque: VecDeque<(f64, f64)>, // (older sample's time, |delta|), oldest first
+    prev_rtt: Option<(f64, f64)>,       // (time, rtt) of the last non-lost sample pushed
+}
+
+impl RingBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            last_time: 0.0,
+            sum: 0.0,
+            count: 0,
+            min_deque: VecDeque::new(),
+            max_deque: VecDeque::new(),
+            jitter_sum: 0.0,
+            jitter_count: 0,
+            jitter_deque: VecDeque::new(),
+            prev_rtt: None,
+        }
+    }
+
+    fn push(&mut self, time: f64, value: f64) {
+        self.samples.push_back([time, value]);
+        if !value.is_nan() {
+            self.sum += value;
+            self.count += 1;
+
+            while self.min_deque.back().is_some_and(|&(_, v)| v >= value) {
+                self.min_deque.pop_back();
+            }
+            self.min_deque.push_back((time, value));
+            while self.max_deque.back().is_some_and(|&(_, v)| v <= value) {
+                self.max_deque.pop_back();
+            }
+            self.max_deque.push_back((time, value));
+
+            if let Some((prev_time, prev_value)) = self.prev_rtt {
+                self.jitter_sum += (value - prev_value).abs();
+                self.jitter_count += 1;
+                self.jitter_deque.push_back((prev_time, (value - prev_value).abs()));
+            }
+            self.prev_rtt = Some((time, value));
+        } else {
+            // A lost ping breaks the jitter pairing so a gap doesn't
+            // create a spurious spike.
+            self.prev_rtt = None;
+        }
+
+        if self.samples.len() > self.capacity {
+            self.evict_front();
+        }
+        self.last_time = time;
+    }
+
+    /// Pop the oldest sample and unwind its contribution to every
+    /// incrementally maintained stat.
+    fn evict_front(&mut self) {
+        let Some([time, value]) = self.samples.pop_front() else {
+            return;
+        };
+        if !value.is_nan() {
+            self.sum -= value;
+            self.count -= 1;
+        }
+        if self.min_deque.front().is_some_and(|&(t, _)| t == time) {
+            self.min_deque.pop_front();
+        }
+        if self.max_deque.front().is_some_and(|&(t, _)| t == time) {
+            self.max_deque.pop_front();
+        }
+        if self.jitter_deque.front().is_some_and(|&(t, _)| t == time) {
+            let (_, diff) = self.jitter_deque.pop_front().unwrap();
+            self.jitter_sum -= diff;
+            self.jitter_count -= 1;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.samples.clear();
+        self.last_time = 0.0;
+        self.sum = 0.0;
+        self.count = 0;
+        self.min_deque.clear();
+        self.max_deque.clear();
+        self.jitter_sum = 0.0;
+        self.jitter_count = 0;
+        self.jitter_deque.clear();
+        self.prev_rtt = None;
+    }
+
+    /// Resize the window, trimming from the front if shrinking.
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.samples.len() > self.capacity {
+            self.evict_front();
+        }
+    }
+
+    /// Ordered oldest-to-newest samples, ready to hand to `Line::new`.
+    fn as_plot_points(&self) -> Vec<[f64; 2]> {
+        self.samples.iter().copied().collect()
+    }
+
+    /// `[head_index, head_index + capacity]`, the sliding X-axis window,
+    /// anchored at the last time this host received a sample.
+    fn bounds(&self) -> (f64, f64) {
+        let head = (self.last_time - self.capacity as f64).max(0.0);
+        (head, head + self.capacity as f64)
+    }
+
+    /// Whether any sample (including lost pings) has been pushed yet.
+    /// `bounds()` isn't meaningful before the first one lands.
+    fn has_samples(&self) -> bool {
+        !self.samples.is_empty()
+    }
+
+    /// `(best, worst, average, jitter)` over the samples currently in the
+    /// window, or `None` if none of them landed (empty window or 100%
+    /// loss).
+    fn window_stats(&self) -> Option<(f64, f64, f64, f64)> {
+        if self.count == 0 {
+            return None;
+        }
+        let best = self.min_deque.front().map(|&(_, v)| v).unwrap();
+        let worst = self.max_deque.front().map(|&(_, v)| v).unwrap();
+        let average = self.sum / self.count as f64;
+        let jitter = if self.jitter_count == 0 {
+            0.0
+        } else {
+            self.jitter_sum / self.jitter_count as f64
+        };
+        Some((best, worst, average, jitter))
+    }
+}
+
+#[cfg(test)]
+mod ring_buffer_tests {
+    use super::RingBuffer;
+
+    #[test]
+    fn push_past_capacity_drops_oldest() {
+        let mut buf = RingBuffer::new(3);
+        for i in 0..5 {
+            buf.push(i as f64, i as f64 * 10.0);
+        }
+        assert_eq!(buf.as_plot_points(), vec![[2.0, 20.0], [3.0, 30.0], [4.0, 40.0]]);
+    }
+
+    #[test]
+    fn set_capacity_shrinks_from_the_front() {
+        let mut buf = RingBuffer::new(5);
+        for i in 0..5 {
+            buf.push(i as f64, i as f64);
+        }
+        buf.set_capacity(2);
+        assert_eq!(buf.as_plot_points(), vec![[3.0, 3.0], [4.0, 4.0]]);
+
+        // Growing back doesn't resurrect dropped samples, but does allow
+        // more future samples to accumulate.
+        buf.set_capacity(4);
+        buf.push(5.0, 5.0);
+        assert_eq!(buf.as_plot_points(), vec![[3.0, 3.0], [4.0, 4.0], [5.0, 5.0]]);
+    }
+
+    #[test]
+    fn bounds_track_a_window_of_capacity_ending_at_last_push() {
+        let mut buf = RingBuffer::new(10);
+        buf.push(25.0, 1.0);
+        assert_eq!(buf.bounds(), (15.0, 25.0));
+    }
+
+    #[test]
+    fn has_samples_is_false_until_the_first_push() {
+        let mut buf = RingBuffer::new(10);
+        assert!(!buf.has_samples());
+        buf.push(0.0, 1.0);
+        assert!(buf.has_samples());
+        buf.clear();
+        assert!(!buf.has_samples());
+    }
+
+    #[test]
+    fn window_stats_is_none_until_a_sample_lands() {
+        let mut buf = RingBuffer::new(3);
+        assert_eq!(buf.window_stats(), None);
+        buf.push(0.0, f64::NAN);
+        assert_eq!(buf.window_stats(), None);
+    }
+
+    #[test]
+    fn window_stats_drops_evicted_extremes() {
+        // The spike at t=0 is the window's worst value; once the window
+        // has advanced past it, worst must fall back to what remains,
+        // not stay pinned to the expired spike.
+        let mut buf = RingBuffer::new(3);
+        buf.push(0.0, 999.0);
+        buf.push(1.0, 10.0);
+        buf.push(2.0, 20.0);
+        assert_eq!(buf.window_stats().unwrap().1, 999.0);
+        buf.push(3.0, 30.0); // evicts t=0, dropping the window's only 999.0 sample
+        let (best, worst, average, _) = buf.window_stats().unwrap();
+        assert_eq!(best, 10.0);
+        assert_eq!(worst, 30.0);
+        assert_eq!(average, 20.0);
+    }
+
+    #[test]
+    fn jitter_resets_across_a_loss_and_evicts_with_its_sample() {
+        let mut buf = RingBuffer::new(3);
+        buf.push(0.0, 10.0);
+        buf.push(1.0, 20.0); // jitter sample: |20-10| = 10
+        buf.push(2.0, f64::NAN); // breaks the pairing, no new jitter sample
+        assert_eq!(buf.window_stats().unwrap().3, 10.0);
+
+        // Evicts t=0, which backed the only jitter sample; the loss at
+        // t=2 means t=3 doesn't form a new one either.
+        buf.push(3.0, 30.0);
+        assert_eq!(buf.window_stats().unwrap().3, 0.0);
+    }
+}
+
+/// p50/p90/p99 latency over the non-lost samples currently in the
+/// window, indexing into a sorted copy at `n*p/100`.
+fn latency_percentiles(ping_times: &[[f64; 2]]) -> Option<(f64, f64, f64)> {
+    let mut rtts: Vec<f64> = ping_times
+        .iter()
+        .map(|&[_, rtt]| rtt)
+        .filter(|rtt| !rtt.is_nan())
+        .collect();
+    if rtts.is_empty() {
+        return None;
+    }
+    rtts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let percentile_at = |p: usize| rtts[(rtts.len() * p / 100).min(rtts.len() - 1)];
+    Some((percentile_at(50), percentile_at(90), percentile_at(99)))
+}
+
+#[cfg(test)]
+mod latency_percentiles_tests {
+    use super::latency_percentiles;
+
+    #[test]
+    fn no_non_lost_samples_yields_none() {
+        let points = [[0.0, f64::NAN], [1.0, f64::NAN]];
+        assert_eq!(latency_percentiles(&points), None);
+    }
+
+    #[test]
+    fn ignores_losses_and_indexes_into_the_sorted_rtts() {
+        let mut points: Vec<[f64; 2]> = (0..10).map(|i| [i as f64, (i + 1) as f64 * 10.0]).collect();
+        points.push([10.0, f64::NAN]);
+        let (p50, p90, p99) = latency_percentiles(&points).unwrap();
+        assert_eq!(p50, 60.0);
+        assert_eq!(p90, 100.0);
+        assert_eq!(p99, 100.0);
+    }
+}
+
+/// One exported sample: its X position on the shared sample clock and its
+/// RTT, or `None` if it was a lost ping.
+struct ExportRow {
+    index: f64,
+    rtt_ms: Option<f64>,
+}
+
+fn export_rows(ping_times: &[[f64; 2]]) -> Vec<ExportRow> {
+    ping_times
+        .iter()
+        .map(|&[index, rtt]| ExportRow {
+            index,
+            rtt_ms: if rtt.is_nan() { None } else { Some(rtt) },
+        })
+        .collect()
+}
+
+/// Turn an address into something safe to use as a file name.
+fn sanitize_filename(address: &str) -> String {
+    address
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect()
+}
+
+/// Write the sample series as one row per sample, followed (when `stats`
+/// is `Some`) by a summary section with best/worst/average/jitter and loss
+/// percentage.
+fn write_csv(
+    path: &Path,
+    rows: &[ExportRow],
+    stats: Option<(f64, f64, f64, f64)>,
+    loss_percent: f64,
+) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    writeln!(file, "index,rtt_ms,lost")?;
+    for row in rows {
+        match row.rtt_ms {
+            Some(rtt) => writeln!(file, "{},{:.2},false", row.index, rtt)?,
+            None => writeln!(file, "{},,true", row.index)?,
+        }
+    }
+    if let Some((best, worst, average, jitter)) = stats {
+        writeln!(file)?;
+        writeln!(file, "best_ms,worst_ms,average_ms,jitter_ms,loss_percent")?;
+        writeln!(
+            file,
+            "{:.2},{:.2},{:.2},{:.2},{:.1}",
+            best, worst, average, jitter, loss_percent
+        )?;
+    }
+    Ok(())
+}
+
+/// Escape `"`, `\` and control characters so a free-text value (e.g. a
+/// user-entered address) can be safely interpolated into a JSON string.
+fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Write the sample series and summary stats as a small hand-built JSON
+/// document (the crate doesn't otherwise depend on a JSON library).
+fn write_json(
+    path: &Path,
+    address: &str,
+    rows: &[ExportRow],
+    stats: Option<(f64, f64, f64, f64)>,
+    loss_percent: f64,
+) -> std::io::Result<()> {
+    let mut json = String::new();
+    json.push_str("{\n");
+    json.push_str(&format!("  \"address\": \"{}\",\n", json_escape(address)));
+    json.push_str("  \"samples\": [\n");
+    for (i, row) in rows.iter().enumerate() {
+        let comma = if i + 1 == rows.len() { "" } else { "," };
+        match row.rtt_ms {
+            Some(rtt) => json.push_str(&format!(
+                "    {{ \"index\": {}, \"rtt_ms\": {:.2}, \"lost\": false }}{}\n",
+                row.index, rtt, comma
+            )),
+            None => json.push_str(&format!(
+                "    {{ \"index\": {}, \"rtt_ms\": null, \"lost\": true }}{}\n",
+                row.index, comma
+            )),
+        }
+    }
+    json.push_str("  ],\n");
+    match stats {
+        Some((best, worst, average, jitter)) => json.push_str(&format!(
+            "  \"summary\": {{ \"best_ms\": {:.2}, \"worst_ms\": {:.2}, \"average_ms\": {:.2}, \"jitter_ms\": {:.2}, \"loss_percent\": {:.1} }}\n",
+            best, worst, average, jitter, loss_percent
+        )),
+        None => json.push_str("  \"summary\": null\n"),
+    }
+    json.push_str("}\n");
+    std::fs::write(path, json)
+}
+
+/// Prompt the user for a destination file and write `host`'s history and
+/// stats there, choosing CSV or JSON based on the extension they picked.
+/// Returns `None` if the user cancelled the dialog.
+fn export_host_history(host: &HostRegistry) -> Option<std::io::Result<()>> {
+    let default_name = format!("{}_ping_history.csv", sanitize_filename(&host.address));
+    let path = rfd::FileDialog::new()
+        .set_file_name(&default_name)
+        .add_filter("CSV", &["csv"])
+        .add_filter("JSON", &["json"])
+        .save_file()?;
+
+    let rows = export_rows(&host.ping_times.as_plot_points());
+    let loss_percent = if host.total_pings == 0 {
+        0.0
+    } else {
+        (host.loss_count as f64 / host.total_pings as f64) * 100.0
+    };
+    let stats = host.stats;
+
+    let result = if path.extension().and_then(|e| e.to_str()) == Some("json") {
+        write_json(&path, &host.address, &rows, stats, loss_percent)
+    } else {
+        write_csv(&path, &rows, stats, loss_percent)
+    };
+    Some(result)
+}
+
+#[cfg(test)]
+mod export_tests {
+    use super::{json_escape, sanitize_filename, write_csv, write_json, ExportRow};
+
+    #[test]
+    fn sanitize_filename_keeps_safe_characters_only() {
+        assert_eq!(sanitize_filename("8.8.8.8"), "8.8.8.8");
+        assert_eq!(sanitize_filename("my-host.example.com"), "my-host.example.com");
+        assert_eq!(sanitize_filename("fe80::1%eth0"), "fe80__1_eth0");
+    }
+
+    #[test]
+    fn json_escape_handles_quotes_and_backslashes() {
+        assert_eq!(json_escape(r#"bad "address" \ here"#), r#"bad \"address\" \\ here"#);
+    }
+
+    fn sample_rows() -> Vec<ExportRow> {
+        vec![
+            ExportRow { index: 0.0, rtt_ms: Some(12.5) },
+            ExportRow { index: 1.0, rtt_ms: None },
+        ]
+    }
+
+    #[test]
+    fn write_csv_handles_a_lost_sample_and_no_stats() {
+        let path = std::env::temp_dir().join("ping_graph_gui_test_export.csv");
+        write_csv(&path, &sample_rows(), None, 50.0).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(contents.contains("0,12.50,false"));
+        assert!(contents.contains("1,,true"));
+        assert!(!contents.contains("best_ms"));
+    }
+
+    #[test]
+    fn write_json_escapes_the_address_and_stays_valid_shaped() {
+        let path = std::env::temp_dir().join("ping_graph_gui_test_export.json");
+        write_json(&path, r#"evil "host""#, &sample_rows(), Some((1.0, 2.0, 1.5, 0.5)), 50.0).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(contents.contains(r#""address": "evil \"host\"","#));
+        assert!(contents.contains(r#""rtt_ms": null, "lost": true"#));
+        assert!(contents.contains(r#""best_ms": 1.00"#));
+    }
+}
+
+/// Owns everything `PingApp` needs to track one host: the receiving end
+/// of its ping channel, its plotted history and its cached stats. One
+/// entry per pinged target, created when a host is added.
+struct HostRegistry {
+    address: String,
+    shared_host: Arc<RwLock<PingHost>>,
+    rx: mpsc::Receiver<f64>,
+    ping_times: RingBuffer,
+    stats: Option<(f64, f64, f64, f64)>,
+    percentiles: Option<(f64, f64, f64)>,
+    loss_count: usize,
+    total_pings: usize,
+}
+
+impl HostRegistry {
+    fn new(
+        address: String,
+        shared_host: Arc<RwLock<PingHost>>,
+        rx: mpsc::Receiver<f64>,
+        window_size: usize,
+    ) -> Self {
+        Self {
+            address,
+            shared_host,
+            rx,
+            ping_times: RingBuffer::new(window_size),
+            stats: None,
+            percentiles: None,
+            loss_count: 0,
+            total_pings: 0,
+        }
+    }
+}
+
 struct PingApp {
-    ping_times: Vec<[f64; 2]>,                 // Stores ping times
-    stats: Option<(f64, f64, f64)>,            // Cached ping statistics: (best, worst, average)
-    ping_times_updated: bool,                  // Flag indicating if ping times were updated
-    last_ping: Instant,                        // Last time a ping was sent
-    shared_data: Arc<RwLock<PingSharedState>>, // Shared address to ping
-    rx: mpsc::Receiver<f64>,                   // Receiver to get ping times from the thread
-    loss_count: usize,                          // Number of lost pings
-    total_pings: usize,                        // Total pings attempted
-    y_axis_auto: bool,                         // Whether Y axis is auto-scaled
-    y_axis_max: f64,                           // Manual Y axis maximum
+    hosts: Vec<HostRegistry>,           // One registry per pinged host
+    address_input: String,              // Comma-separated address text field
+    ping_times_updated: bool,           // Flag indicating if any host's data was updated
+    last_ping: Instant,                 // Last time the Reset button was pressed
+    sample_clock: f64,                  // Shared X-axis clock, ticked once per incoming sample across all hosts
+    shared_data: Arc<RwLock<PingSharedState>>, // Ping interval/timeout read by every worker thread
+    window_size: usize,                 // Ring buffer capacity, shared by all hosts
+    interval_ms: u64,                   // Local mirror of shared_data's ping interval
+    timeout_ms: u64,                    // Local mirror of shared_data's ping timeout
+    y_axis_auto: bool,                  // Whether Y axis is auto-scaled
+    y_axis_max: f64,                    // Manual Y axis maximum
+    export_error: Option<String>,       // Error from the last failed export, if any
 }
 
 impl Default for PingApp {
     fn default() -> Self {
-        let (_, rx) = mpsc::channel(); // Initialize both sender and receiver
         Self {
-            ping_times: Vec::new(),
-            stats: None,               // No stats initially
+            hosts: Vec::new(),
+            address_input: "8.8.8.8".to_string(),
             ping_times_updated: false, // No updates initially
             last_ping: Instant::now(),
+            sample_clock: 0.0,
             shared_data: Arc::new(RwLock::new(PingSharedState {
-                address: "8.8.8.8".to_string(), // Default address
-                error: "".to_string(),
+                interval_ms: PING_INTERVAL_MS,
+                timeout_ms: PING_TIMEOUT_MS,
             })),
-            rx, // Set up receiver for ping times
-            loss_count: 0,
-            total_pings: 0,
+            window_size: DEFAULT_WINDOW_SIZE,
+            interval_ms: PING_INTERVAL_MS,
+            timeout_ms: PING_TIMEOUT_MS,
             y_axis_auto: true,
             y_axis_max: 200.0,
+            export_error: None,
         }
     }
 }
 
 impl PingApp {
-    fn new(shared_data: Arc<RwLock<PingSharedState>>, rx: mpsc::Receiver<f64>) -> Self {
+    fn new(shared_data: Arc<RwLock<PingSharedState>>, hosts: Vec<HostRegistry>) -> Self {
+        let address_input = hosts
+            .iter()
+            .map(|h| h.address.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (interval_ms, timeout_ms) = {
+            let data = shared_data.read().unwrap();
+            (data.interval_ms, data.timeout_ms)
+        };
         Self {
+            hosts,
+            address_input,
             shared_data,
-            rx,
+            interval_ms,
+            timeout_ms,
             ..Default::default()
         }
     }
+
+    /// Add any addresses in `address_input` that aren't already being
+    /// pinged, spawning a worker thread for each (handed that host's own
+    /// `PingHost` and the shared interval/timeout settings) and registering
+    /// it in `hosts`.
+    fn add_hosts_from_input(&mut self) {
+        for address in self.address_input.split(',') {
+            let address = address.trim();
+            if address.is_empty() {
+                continue;
+            }
+            if self.hosts.iter().any(|h| h.address == address) {
+                continue;
+            }
+
+            let shared_host = Arc::new(RwLock::new(PingHost {
+                address: address.to_string(),
+                error: "".to_string(),
+            }));
+            let (tx, rx) = mpsc::channel();
+            spawn_ping_worker(Arc::clone(&shared_host), Arc::clone(&self.shared_data), tx);
+
+            self.hosts.push(HostRegistry::new(
+                address.to_string(),
+                shared_host,
+                rx,
+                self.window_size,
+            ));
+        }
+    }
 }
 
 impl eframe::App for PingApp {
@@ -57,80 +641,155 @@ impl eframe::App for PingApp {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("Ping Graph");
 
-            let mut address = {
-                let shared_data = self.shared_data.read().unwrap();
-                shared_data.address.clone()
-            };
-
             ui.horizontal(|ui| {
-                ui.label("Address to ping:");
-                if ui.text_edit_singleline(&mut address).changed() {
-                    let mut shared_data = self.shared_data.write().unwrap();
-                    shared_data.address = address;
+                ui.label("Addresses to ping (comma-separated):");
+                let response = ui.text_edit_singleline(&mut self.address_input);
+                let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button("Add host").clicked() || submitted {
+                    self.add_hosts_from_input();
                 }
                 if ui.button("Reset").clicked() {
-                    self.ping_times.clear();
+                    for host in &mut self.hosts {
+                        host.ping_times.clear();
+                        host.stats = None;
+                        host.percentiles = None;
+                        host.loss_count = 0;
+                        host.total_pings = 0;
+                    }
+                    self.sample_clock = 0.0;
                     self.last_ping = Instant::now();
                     self.ping_times_updated = true;
                 }
-                let shared_data = self.shared_data.read().unwrap();
-                let mut err = shared_data.error.clone();
-                err.truncate(90);
-                ui.label(egui::RichText::new(err).color(egui::Color32::RED));
             });
 
-            // Check for new ping times
-            while let Ok(ping_time) = self.rx.try_recv() {
-                let time = self.ping_times.len() as f64;
-                self.total_pings += 1;
-                if ping_time.is_nan() {
-                    // record a lost ping; keep a NaN entry so plotting can show gaps
-                    self.loss_count += 1;
-                    self.ping_times.push([time, f64::NAN]);
-                } else {
-                    self.ping_times.push([time, ping_time]);
+            for host in &self.hosts {
+                let err = {
+                    let shared_host = host.shared_host.read().unwrap();
+                    let mut err = shared_host.error.clone();
+                    err.truncate(90);
+                    err
+                };
+                if !err.is_empty() {
+                    ui.label(
+                        egui::RichText::new(format!("{}: {}", host.address, err))
+                            .color(egui::Color32::RED),
+                    );
+                }
+            }
+
+            // Check for new ping times on every host. All hosts tick the
+            // same `sample_clock` so a host added mid-session shares the
+            // same X-axis coordinate space as hosts already running,
+            // instead of each starting its own window at zero.
+            for host in &mut self.hosts {
+                while let Ok(ping_time) = host.rx.try_recv() {
+                    self.sample_clock += 1.0;
+                    host.total_pings += 1;
+                    if ping_time.is_nan() {
+                        // record a lost ping; keep a NaN entry so plotting can show gaps
+                        host.loss_count += 1;
+                        host.ping_times.push(self.sample_clock, f64::NAN);
+                    } else {
+                        host.ping_times.push(self.sample_clock, ping_time);
+                    }
+                    self.ping_times_updated = true;
                 }
-                // keep all samples (user requested to retain all data)
-                self.ping_times_updated = true;
             }
 
             if self.ping_times_updated {
                 ctx.request_repaint();
-                self.stats = calculate_ping_stats(&self.ping_times);
+                for host in &mut self.hosts {
+                    host.percentiles = latency_percentiles(&host.ping_times.as_plot_points());
+                    host.stats = host.ping_times.window_stats();
+                }
                 self.ping_times_updated = false;
             }
 
-            let (_, worst, _) = self.stats.unwrap_or((0.0, 100.0, 0.0));
+            let worst = self
+                .hosts
+                .iter()
+                .filter_map(|h| h.stats)
+                .map(|(_, worst, _, _)| worst)
+                .fold(0.0_f64, f64::max);
 
             Plot::new("ping_plot")
                 .view_aspect(2.0)
                 .allow_scroll(false)
                 .allow_zoom(false)
                 .allow_drag(false)
+                .legend(Legend::default())
                 .show(ui, |plot_ui| {
-                    // Main ping line
-                    plot_ui.line(Line::new("ping_times", self.ping_times.clone()));
-
-                    let size = self.ping_times.len() as f64;
+                    let mut window_min = f64::INFINITY;
+                    let mut window_max = f64::NEG_INFINITY;
+                    for (i, host) in self.hosts.iter().enumerate() {
+                        let color = HOST_COLORS[i % HOST_COLORS.len()];
+                        plot_ui.line(
+                            Line::new(host.ping_times.as_plot_points())
+                                .name(host.address.clone())
+                                .color(color),
+                        );
+                        // A host with no samples yet has bounds() pinned
+                        // at [0, window_size], which would otherwise drag
+                        // window_min down to 0 and stretch every other
+                        // host's axis until its first ping lands.
+                        if host.ping_times.has_samples() {
+                            let (head, tail) = host.ping_times.bounds();
+                            window_min = window_min.min(head);
+                            window_max = window_max.max(tail);
+                        }
+                    }
+                    if !window_min.is_finite() {
+                        window_min = 0.0;
+                        window_max = self.window_size as f64;
+                    }
 
                     // Determine Y axis max: auto or manual
                     let y_max = if self.y_axis_auto { worst + 10.0 } else { self.y_axis_max };
 
-                    plot_ui.set_plot_bounds(PlotBounds::from_min_max([0.0, 0.0], [size, y_max]));
+                    plot_ui.set_plot_bounds(PlotBounds::from_min_max(
+                        [window_min, 0.0],
+                        [window_max, y_max],
+                    ));
                 });
 
-            if let Some((best, worst, average)) = self.stats {
-                let loss_percent = if self.total_pings == 0 {
+            if self.hosts.is_empty() {
+                ui.label("No hosts added yet.");
+            }
+            if let Some(err) = &self.export_error {
+                ui.label(egui::RichText::new(err).color(egui::Color32::RED));
+            }
+            for host in &self.hosts {
+                let loss_percent = if host.total_pings == 0 {
                     0.0
                 } else {
-                    (self.loss_count as f64 / self.total_pings as f64) * 100.0
+                    (host.loss_count as f64 / host.total_pings as f64) * 100.0
                 };
-                ui.label(format!(
-                    "{:.2}ms best, {:.2}ms worst, {:.2}ms average — Loss: {:.1}% ({}/{})",
-                    best, worst, average, loss_percent, self.loss_count, self.total_pings
-                ));
-            } else {
-                ui.label("No ping times available.");
+                ui.horizontal(|ui| {
+                    if let Some((best, worst, average, jitter)) = host.stats {
+                        ui.label(format!(
+                            "{}: {:.2}ms best, {:.2}ms worst, {:.2}ms average, {:.2}ms jitter — Loss: {:.1}% ({}/{})",
+                            host.address, best, worst, average, jitter, loss_percent, host.loss_count, host.total_pings
+                        ));
+                    } else {
+                        ui.label(format!("{}: No ping times available.", host.address));
+                    }
+                    // Shown whenever the host has sent at least one ping,
+                    // even if every one of them was lost — write_csv/write_json
+                    // already handle a `None` summary correctly.
+                    if host.total_pings > 0 && ui.button("Export").clicked() {
+                        match export_host_history(host) {
+                            Some(Ok(())) => self.export_error = None,
+                            Some(Err(e)) => self.export_error = Some(format!("Export failed: {}", e)),
+                            None => {} // user cancelled the dialog
+                        }
+                    }
+                });
+                if let Some((p50, p90, p99)) = host.percentiles {
+                    ui.label(format!(
+                        "    p50 {:.2}ms, p90 {:.2}ms, p99 {:.2}ms",
+                        p50, p90, p99
+                    ));
+                }
             }
 
             // Controls for Y axis
@@ -140,7 +799,35 @@ impl eframe::App for PingApp {
                     ui.add(egui::Slider::new(&mut self.y_axis_max, 10.0..=2000.0).text("Y max"));
                 }
             });
-            
+
+            // Control for the ring buffer window size, shared by all hosts
+            ui.horizontal(|ui| {
+                if ui
+                    .add(egui::Slider::new(&mut self.window_size, 50..=5000).text("Window size"))
+                    .changed()
+                {
+                    for host in &mut self.hosts {
+                        host.ping_times.set_capacity(self.window_size);
+                    }
+                }
+            });
+
+            // Controls for the worker threads' send cadence and per-ping timeout
+            ui.horizontal(|ui| {
+                if ui
+                    .add(egui::Slider::new(&mut self.interval_ms, 100..=10_000).text("Interval (ms)"))
+                    .changed()
+                {
+                    self.shared_data.write().unwrap().interval_ms = self.interval_ms;
+                }
+                if ui
+                    .add(egui::Slider::new(&mut self.timeout_ms, 100..=10_000).text("Timeout (ms)"))
+                    .changed()
+                {
+                    self.shared_data.write().unwrap().timeout_ms = self.timeout_ms;
+                }
+            });
+
             if ctx.input(|i| i.focused) {
                 std::thread::sleep(Duration::from_millis(6));
             } else {
@@ -152,110 +839,96 @@ impl eframe::App for PingApp {
         ctx.request_repaint();
     }
 }
-struct PingSharedState {
-    address: String,
-    error: String,
-}
-
-fn main() -> Result<(), eframe::Error> {
-    let options: eframe::NativeOptions = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([800.0, 600.0])
-            .with_min_inner_size([300.0, 220.0]),
-        ..Default::default()
-    };
-
-    let shared_ping_data: Arc<RwLock<PingSharedState>> = Arc::new(RwLock::new(PingSharedState {
-        address: "8.8.8.8".to_string(),
-        error: "".to_string(),
-    }));
-    let shared_ping_data_for_thread = Arc::clone(&shared_ping_data);
 
-    let (tx, rx) = std::sync::mpsc::channel();
+/// Spawn the worker thread that repeatedly pings `shared_host`'s address
+/// and reports each round-trip time (or `f64::NAN` on a failed or timed
+/// out ping) over `tx`. The send cadence and per-ping timeout are read
+/// from `shared_state` on every iteration, so adjusting the sliders
+/// takes effect on the next attempt.
+fn spawn_ping_worker(
+    shared_host: Arc<RwLock<PingHost>>,
+    shared_state: Arc<RwLock<PingSharedState>>,
+    tx: mpsc::Sender<f64>,
+) -> thread::JoinHandle<()> {
     thread::spawn(move || loop {
-        let shared_data = shared_ping_data_for_thread.read().unwrap();
-        let start = Instant::now();
-        let address = &shared_data.address.clone();
-        drop(shared_data);
-        let mut success = false;
+        let host = shared_host.read().unwrap();
+        let address = host.address.clone();
+        drop(host);
+        let timeout = Duration::from_millis(shared_state.read().unwrap().timeout_ms);
         match (address.as_str(), 0).to_socket_addrs() {
             Ok(mut addrs) => {
                 if let Some(sock_addr) = addrs.next() {
                     let ip = sock_addr.ip();
-                    match ping(ip, None, None, None, None, None) {
+                    // Start the clock right before the ICMP round trip itself,
+                    // not before DNS resolution, so the measured RTT is real.
+                    let start = Instant::now();
+                    match ping(ip, Some(timeout), None, None, None, None) {
                         Ok(_) => {
                             let duration = start.elapsed();
                             let _ = tx.send(duration.as_millis() as f64);
-                            let mut shared_data = shared_ping_data_for_thread.write().unwrap();
-                            shared_data.error = "".to_string();
-                            success = true;
+                            let mut host = shared_host.write().unwrap();
+                            host.error = "".to_string();
                         }
                         Err(e) => {
-                            // send NaN to indicate a lost ping
+                            // a timeout or any other failure counts as a lost ping
                             let _ = tx.send(f64::NAN);
-                            let mut shared_data = shared_ping_data_for_thread.write().unwrap();
-                            shared_data.error = format!("Ping failed: {}", e);
+                            let mut host = shared_host.write().unwrap();
+                            host.error = format!("Ping failed: {}", e);
                         }
                     }
                 } else {
                     // Could not resolve; report as lost ping
                     let _ = tx.send(f64::NAN);
-                    let mut shared_data = shared_ping_data_for_thread.write().unwrap();
-                    shared_data.error = format!("Could not resolve address: {}", address);
+                    let mut host = shared_host.write().unwrap();
+                    host.error = format!("Could not resolve address: {}", address);
                 }
             }
             Err(e) => {
                 // Invalid address resolution; report as lost ping
                 let _ = tx.send(f64::NAN);
-                let mut shared_data = shared_ping_data_for_thread.write().unwrap();
-                shared_data.error = format!("Invalid address: {}. Error: {}", address, e);
+                let mut host = shared_host.write().unwrap();
+                host.error = format!("Invalid address: {}. Error: {}", address, e);
             }
         }
 
-        if success {
-            thread::sleep(Duration::from_secs(1));
-        } else {
-            thread::sleep(Duration::from_secs(2));
-        }
-    });
+        let interval = Duration::from_millis(shared_state.read().unwrap().interval_ms);
+        thread::sleep(interval);
+    })
+}
+
+fn main() -> Result<(), eframe::Error> {
+    let options: eframe::NativeOptions = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size([800.0, 600.0])
+            .with_min_inner_size([300.0, 220.0]),
+        ..Default::default()
+    };
+
+    let shared_ping_data: Arc<RwLock<PingSharedState>> = Arc::new(RwLock::new(PingSharedState {
+        interval_ms: PING_INTERVAL_MS,
+        timeout_ms: PING_TIMEOUT_MS,
+    }));
+
+    // Start with the default target pinging immediately.
+    let default_host = Arc::new(RwLock::new(PingHost {
+        address: "8.8.8.8".to_string(),
+        error: "".to_string(),
+    }));
+    let (tx, rx) = mpsc::channel();
+    spawn_ping_worker(Arc::clone(&default_host), Arc::clone(&shared_ping_data), tx);
+    let host_registries = vec![HostRegistry::new(
+        "8.8.8.8".to_string(),
+        default_host,
+        rx,
+        DEFAULT_WINDOW_SIZE,
+    )];
 
     let shared_ping_data_for_app = Arc::clone(&shared_ping_data);
     eframe::run_native(
         "Ping Graph",
         options,
-        Box::new(|_cc| Ok(Box::new(PingApp::new(shared_ping_data_for_app, rx)))),
+        Box::new(|_cc| {
+            Ok(Box::new(PingApp::new(shared_ping_data_for_app, host_registries)))
+        }),
     )
 }
-
-fn calculate_ping_stats(ping_times: &[[f64; 2]]) -> Option<(f64, f64, f64)> {
-    if ping_times.is_empty() {
-        return None;
-    }
-
-    let mut min_ping = f64::INFINITY;
-    let mut max_ping = f64::NEG_INFINITY;
-    let mut total_ping = 0.0;
-    let mut count = 0;
-
-    for &[_time, ping] in ping_times.iter() {
-        if ping.is_nan() {
-            continue;
-        }
-        if ping < min_ping {
-            min_ping = ping;
-        }
-        if ping > max_ping {
-            max_ping = ping;
-        }
-        total_ping += ping;
-        count += 1;
-    }
-
-    if count == 0 {
-        return None;
-    }
-
-    let avg_ping = total_ping / count as f64;
-
-    Some((min_ping, max_ping, avg_ping))
-}